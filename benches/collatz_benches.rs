@@ -1,163 +1,98 @@
-#![feature(test)]
-
 extern crate collatz;
-extern crate test;
 
-use collatz::{bitwise, faster_shortcut, BatchSummary};
+use collatz::bench::{bench, fmt_bench_samples};
+use collatz::{bitwise, faster_shortcut, recursive, shortcut, simple, BatchSummary};
+use std::hint::black_box;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
-use test::Bencher;
 
 static TEST_SIZE: usize = 5_000;
 
+/// Print one routine's timing summary, labelled with `name`. Each call of a
+/// routine here sweeps a `TEST_SIZE`-number range, so per-call times are
+/// divided by `TEST_SIZE` to give the per-number figures printed.
+fn report(name: &str, mut samples: collatz::bench::BenchSamples) {
+    for ns in samples.ns_per_call.iter_mut() {
+        *ns /= TEST_SIZE as f32;
+    }
+    println!("{:<28} {}", name, fmt_bench_samples(&samples));
+}
+
 fn test_performance(f: fn(usize) -> usize, start: usize, end: usize) {
     // The "faster" method can skip even numbers since those can't start a cycle.
-    if f == faster_shortcut || f == bitwise {
+    if f == bitwise {
         let mut nums = start..end;
         if start % 2 == 0 {
             nums.next();
         }
         for n in nums.step_by(2) {
-            f(n);
+            black_box(f(n));
         }
     } else {
         for n in start..end {
-            f(n);
+            black_box(f(n));
         }
     }
 }
 
-/// Recursive impl benchmark starting at 1
-#[bench]
-fn bench_recursive_small(b: &mut Bencher) {
-    let start = 1;
-    b.iter(|| test_performance(collatz::recursive, start, start + TEST_SIZE));
-}
-
-/// Recursive impl benchmark starting at 1,000,000
-#[bench]
-fn bench_recursive_mid(b: &mut Bencher) {
-    let start = 1_000_000;
-    b.iter(|| test_performance(collatz::recursive, start, start + TEST_SIZE));
-}
-
-/// Recursive impl benchmark starting at 1,000,000,000
-#[bench]
-fn bench_recursive_big(b: &mut Bencher) {
-    let start = 1_000_000_000;
-    b.iter(|| test_performance(collatz::recursive, start, start + TEST_SIZE));
-}
-
-/// simple impl benchmark starting at 1
-#[bench]
-fn bench_simple_small(b: &mut Bencher) {
-    let start = 1;
-    b.iter(|| test_performance(collatz::simple, start, start + TEST_SIZE));
-}
-
-/// simple impl benchmark starting at 1,000,000
-#[bench]
-fn bench_simple_mid(b: &mut Bencher) {
-    let start = 1_000_000;
-    b.iter(|| test_performance(collatz::simple, start, start + TEST_SIZE));
-}
-
-/// simple impl benchmark starting at 1,000,000,000
-#[bench]
-fn bench_simple_big(b: &mut Bencher) {
-    let start = 1_000_000_000;
-    b.iter(|| test_performance(collatz::simple, start, start + TEST_SIZE));
-}
-
-/// Shortcut impl benchmark starting at 1
-#[bench]
-fn bench_shortcut_small(b: &mut Bencher) {
-    let start = 1;
-    b.iter(|| test_performance(collatz::shortcut, start, start + TEST_SIZE));
-}
-
-/// Shortcut impl benchmark starting at 1,000,000
-#[bench]
-fn bench_shortcut_mid(b: &mut Bencher) {
-    let start = 1_000_000;
-    b.iter(|| test_performance(collatz::shortcut, start, start + TEST_SIZE));
-}
-
-/// Shortcut impl benchmark starting at 1,000,000,000
-#[bench]
-fn bench_shortcut_big(b: &mut Bencher) {
-    let start = 1_000_000_000;
-    b.iter(|| test_performance(collatz::shortcut, start, start + TEST_SIZE));
-}
-
-/// Faster shortcut impl benchmark starting at 1
-#[bench]
-fn bench_faster_shortcut_small(b: &mut Bencher) {
-    let start = 1;
-    b.iter(|| test_performance(collatz::faster_shortcut, start, start + TEST_SIZE));
-}
-
-/// Faster shortcut impl benchmark starting at 1,000,000
-#[bench]
-fn bench_faster_shortcut_mid(b: &mut Bencher) {
-    let start = 1_000_000;
-    b.iter(|| test_performance(collatz::faster_shortcut, start, start + TEST_SIZE));
-}
-
-/// Faster shortcut impl benchmark starting at 1,000,000,000
-#[bench]
-fn bench_faster_shortcut_big(b: &mut Bencher) {
-    let start = 1_000_000_000;
-    b.iter(|| test_performance(collatz::faster_shortcut, start, start + TEST_SIZE));
+fn test_faster_shortcut_performance(start: usize, end: usize) {
+    // The "faster" method can skip even numbers since those can't start a cycle.
+    let mut nums = start..end;
+    if start % 2 == 0 {
+        nums.next();
+    }
+    for n in nums.step_by(2) {
+        black_box(faster_shortcut(n));
+    }
 }
 
-/// Faster shortcut impl benchmark starting at 1
-#[bench]
-fn bench_bitwise_small(b: &mut Bencher) {
-    let start = 1;
-    b.iter(|| test_performance(collatz::bitwise, start, start + TEST_SIZE));
+fn test_solve_performance(start: usize, end: usize) {
+    let (tx, _): (Sender<BatchSummary>, Receiver<BatchSummary>) = mpsc::channel();
+    collatz::solve(start, end, tx, 209, 24);
+    // let (tx, _): (Sender<(usize, usize)>, Receiver<(usize, usize)>) = mpsc::channel();
+    // collatz::solve_no_batching(start, end, tx, 24);
 }
 
-/// Faster shortcut impl benchmark starting at 1,000,000
-#[bench]
-fn bench_bitwise_mid(b: &mut Bencher) {
-    let start = 1_000_000;
-    b.iter(|| test_performance(collatz::bitwise, start, start + TEST_SIZE));
-}
+fn main() {
+    for (label, start) in [("small", 1), ("mid", 1_000_000), ("big", 1_000_000_000)] {
+        report(
+            &format!("recursive_{label}"),
+            bench(|| test_performance(recursive, start, start + TEST_SIZE)),
+        );
+    }
 
-/// Faster shortcut impl benchmark starting at 1,000,000,000
-#[bench]
-fn bench_bitwise_big(b: &mut Bencher) {
-    let start = 1_000_000_000;
-    b.iter(|| test_performance(collatz::bitwise, start, start + TEST_SIZE));
-}
+    for (label, start) in [("small", 1), ("mid", 1_000_000), ("big", 1_000_000_000)] {
+        report(
+            &format!("simple_{label}"),
+            bench(|| test_performance(simple, start, start + TEST_SIZE)),
+        );
+    }
 
-fn test_solve_performance(start: usize, end: usize, b: &mut Bencher) {
-    b.iter(|| {
-        let (tx, _): (Sender<BatchSummary>, Receiver<BatchSummary>) = mpsc::channel();
-        collatz::solve(start, end, tx, 209, 24);
-        // let (tx, _): (Sender<(usize, usize)>, Receiver<(usize, usize)>) = mpsc::channel();
-        // collatz::solve_no_batching(start, end, tx, 24);
-    });
-}
+    for (label, start) in [("small", 1), ("mid", 1_000_000), ("big", 1_000_000_000)] {
+        report(
+            &format!("shortcut_{label}"),
+            bench(|| test_performance(shortcut, start, start + TEST_SIZE)),
+        );
+    }
 
-/// Multithreaded solve benchmark starting at 1
-#[bench]
-fn bench_solve_small(b: &mut Bencher) {
-    let start = 1;
-    test_solve_performance(start, start + TEST_SIZE, b);
-}
+    for (label, start) in [("small", 1), ("mid", 1_000_000), ("big", 1_000_000_000)] {
+        report(
+            &format!("faster_shortcut_{label}"),
+            bench(|| test_faster_shortcut_performance(start, start + TEST_SIZE)),
+        );
+    }
 
-/// Multithreaded solve benchmark starting at 1,000,000
-#[bench]
-fn bench_solve_mid(b: &mut Bencher) {
-    let start = 1_000_000;
-    test_solve_performance(start, start + TEST_SIZE, b);
-}
+    for (label, start) in [("small", 1), ("mid", 1_000_000), ("big", 1_000_000_000)] {
+        report(
+            &format!("bitwise_{label}"),
+            bench(|| test_performance(bitwise, start, start + TEST_SIZE)),
+        );
+    }
 
-/// Multithreaded solve benchmark (1,000,000,000..1,000,005,000)
-#[bench]
-fn bench_solve_big(b: &mut Bencher) {
-    let start = 1_000_000_000;
-    test_solve_performance(start, start + TEST_SIZE, b);
+    for (label, start) in [("small", 1), ("mid", 1_000_000), ("big", 1_000_000_000)] {
+        report(
+            &format!("solve_{label}"),
+            bench(|| test_solve_performance(start, start + TEST_SIZE)),
+        );
+    }
 }
@@ -0,0 +1,63 @@
+//! A small, stable-compatible measurement harness, modeled on the approach
+//! the stable `bencher` port of libtest and Criterion use, so routines can
+//! be timed without nightly's `#![feature(test)]`.
+
+use crate::stats::Summary;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use thousands::Separable;
+
+/// How long a single timed sample (of `n` calls) should take.
+const SAMPLE_TARGET: Duration = Duration::from_millis(10);
+/// How long to run discarded warm-up samples before timing for real.
+const WARMUP_TIME: Duration = Duration::from_secs(3);
+/// How many timed samples to collect.
+const NUM_SAMPLES: usize = 100;
+
+/// Per-call nanosecond timings collected by [`bench`].
+pub struct BenchSamples {
+    pub ns_per_call: Vec<f32>,
+}
+
+/// Time `n` calls to `f`, discarding their results, and return the total
+/// elapsed wall time.
+fn time_calls<T>(f: &mut impl FnMut() -> T, n: u64) -> Duration {
+    let start = Instant::now();
+    for _ in 0..n {
+        black_box(f());
+    }
+    start.elapsed()
+}
+
+/// Benchmark `f`: probe it once to estimate its per-call cost, pick an
+/// iteration count `n` so a sample takes roughly [`SAMPLE_TARGET`], warm up
+/// for [`WARMUP_TIME`] discarding results, then collect [`NUM_SAMPLES`]
+/// timed samples of `n` calls each.
+pub fn bench<T>(mut f: impl FnMut() -> T) -> BenchSamples {
+    let probe_ns = time_calls(&mut f, 1).as_nanos().max(1) as u64;
+    let n = (SAMPLE_TARGET.as_nanos() as u64 / probe_ns).max(1);
+
+    let warmup_start = Instant::now();
+    while warmup_start.elapsed() < WARMUP_TIME {
+        time_calls(&mut f, n);
+    }
+
+    let ns_per_call = (0..NUM_SAMPLES)
+        .map(|_| time_calls(&mut f, n).as_nanos() as f32 / n as f32)
+        .collect();
+
+    BenchSamples { ns_per_call }
+}
+
+/// Format `samples` as a `stats::Summary` plus a numbers/second throughput
+/// figure, analogous to the `mb_s` field in libtest's `fmt_bench_samples`.
+pub fn fmt_bench_samples(samples: &BenchSamples) -> String {
+    let summary = Summary::new(&samples.ns_per_call);
+    let numbers_per_sec = 1_000_000_000.0 / summary.median;
+    format!(
+        "{:>12.2} ns/number (+/- {:.2})  {:>16} numbers/s",
+        summary.median,
+        summary.std_dev,
+        (numbers_per_sec as u64).separate_with_commas(),
+    )
+}
@@ -1,8 +1,12 @@
 use std::cmp::{max, min};
+use std::hint::black_box;
 use std::sync::mpsc::Sender;
 use std::time::SystemTime;
 use threadpool::ThreadPool;
 
+pub mod bench;
+pub mod stats;
+
 /// Summary of solving a batch of 3x+1 numbers
 pub struct BatchSummary {
     /// Starting number
@@ -11,6 +15,8 @@ pub struct BatchSummary {
     pub start_time: SystemTime,
     pub end_time: SystemTime,
     pub max_steps: usize,
+    /// The number in `start..end` whose step count is `max_steps`
+    pub max_steps_num: usize,
 }
 
 /// Recursive implementation of Collatz. Returns number of iterations to reach 1.
@@ -61,24 +67,27 @@ pub fn shortcut(num: usize) -> usize {
 /// if the algorithm returns a number less than N, we can exit as we know
 /// that number has already been solved.
 ///
-/// This messes up the `count` variable beyond recognition. It is kept
-/// to keep the function signature the same, and ensure the compiler
-/// doesn't get ahead of itself and optimize the function out of existence.
+/// Because of that early exit, this can't report an accurate step count.
+/// Instead it reports whether `num`'s trajectory ever climbed higher than
+/// `num` itself before dropping back below it -- i.e. whether `num` is a
+/// new altitude record rather than an immediate descent to an
+/// already-solved number.
 #[allow(dead_code)]
-pub fn faster_shortcut(num: usize) -> usize {
+pub fn faster_shortcut(num: usize) -> bool {
     // Special case: can't get to < 1.
     if num == 1 {
-        return 1;
+        return false;
     }
-    let mut count = 0;
     let mut curr_num = num;
+    let mut max_num = num;
     while curr_num >= num {
-        (curr_num, count) = match curr_num {
-            curr_num if curr_num % 2 == 0 => (curr_num / 2, count + 1),
-            _ => ((3 * curr_num + 1) / 2, count + 2),
+        curr_num = match curr_num {
+            curr_num if curr_num % 2 == 0 => curr_num / 2,
+            _ => (3 * curr_num + 1) / 2,
         };
+        max_num = max(max_num, curr_num);
     }
-    count
+    max_num > num
 }
 
 /// Implementation based on https://en.wikipedia.org/wiki/Collatz_conjecture#As_an_abstract_machine_that_computes_in_base_two
@@ -100,7 +109,7 @@ pub fn bitwise(num: usize) -> usize {
     count
 }
 
-/// Solver that doesn't use batching.
+/// Solves without batching.
 ///
 /// Kept around to demonstrate a simpler threadpool implementation.
 pub fn solve_no_batching(
@@ -121,27 +130,30 @@ pub fn solve_no_batching(
     pool.join();
 }
 
-/// Solve a set of numbers using a threadpool and batches.
-pub fn solve(
+/// Dispatch `start..end` onto `pool` in `batch_size`-sized chunks, sending a
+/// `BatchSummary` for each to `output_channel` as it completes. Does not
+/// join the pool, so callers can dispatch further ranges before waiting.
+fn dispatch_batches(
+    pool: &ThreadPool,
+    output_channel: &Sender<BatchSummary>,
     start: usize,
     end: usize,
-    output_channel: Sender<BatchSummary>,
     batch_size: usize,
-    threads: usize,
 ) {
-    let pool = ThreadPool::new(threads);
-
     let mut batch_start = start;
     while batch_start < end {
         let batch_end = min(batch_start + batch_size, end);
         let output_channel = output_channel.clone();
         pool.execute(move || {
             let mut max_steps = 0;
+            let mut max_steps_num = batch_start;
             let start_time = SystemTime::now();
             for num in batch_start..batch_end {
-                // max steps is mildly interesting, but really i'm making sure
-                // the compiler doesn't make this function call disappear.
-                max_steps = max(max_steps, bitwise(num));
+                let steps = black_box(bitwise(num));
+                if steps > max_steps {
+                    max_steps = steps;
+                    max_steps_num = num;
+                }
             }
             // Send a completion summary to the output channel
             output_channel
@@ -151,14 +163,51 @@ pub fn solve(
                     start_time,
                     end_time: SystemTime::now(),
                     max_steps,
+                    max_steps_num,
                 })
                 .expect("channel broken!");
         });
         batch_start = batch_end;
     }
+}
+
+/// Solve a set of numbers using a threadpool and batches.
+pub fn solve(
+    start: usize,
+    end: usize,
+    output_channel: Sender<BatchSummary>,
+    batch_size: usize,
+    threads: usize,
+) {
+    let pool = ThreadPool::new(threads);
+    dispatch_batches(&pool, &output_channel, start, end, batch_size);
     pool.join();
 }
 
+/// A solver that keeps its `ThreadPool` and output channel alive across
+/// calls, so repeated ranges (e.g. successive infini-batches) don't pay
+/// pool and channel setup cost on every call.
+pub struct Solver {
+    pool: ThreadPool,
+    output_channel: Sender<BatchSummary>,
+}
+
+impl Solver {
+    pub fn new(threads: usize, output_channel: Sender<BatchSummary>) -> Solver {
+        Solver {
+            pool: ThreadPool::new(threads),
+            output_channel,
+        }
+    }
+
+    /// Solve `start..end` in `batch_size`-sized chunks, reusing this
+    /// solver's pool and output channel. Blocks until the range is done.
+    pub fn run_range(&self, start: usize, end: usize, batch_size: usize) {
+        dispatch_batches(&self.pool, &self.output_channel, start, end, batch_size);
+        self.pool.join();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,11 +223,13 @@ mod tests {
 
     // Generated test data from running "faster" implementation. Ensures answers don't change, but
     // isn't validated to be correct.
-    static FASTER_ANSWERS: &'static [usize] = &[
-        1, 1, 6, 1, 3, 1, 11, 1, 3, 1, 8, 1, 3, 1, 11, 1, 3, 1, 6, 1, 3, 1, 8, 1, 3, 1, 96, 1, 3,
-        1, 91, 1, 3, 1, 6, 1, 3, 1, 13, 1, 3, 1, 8, 1, 3, 1, 88, 1, 3, 1, 6, 1, 3, 1, 8, 1, 3, 1,
-        11, 1, 3, 1, 88, 1, 3, 1, 6, 1, 3, 1, 83, 1, 3, 1, 8, 1, 3, 1, 13, 1, 3, 1, 6, 1, 3, 1, 8,
-        1, 3, 1, 73, 1, 3, 1, 13, 1, 3, 1, 6, 1,
+    static FASTER_ANSWERS: &'static [bool] = &[
+        false, false, true, false, true, false, true, false, true, false, true, false, true,
+        false, true, false, true, false, true, false, true, false, true, false, true, false, true,
+        false, true, false, true, false, true, false, true, false, true, false, true, false, true,
+        false, true, false, true, false, true, false, true, false, true, false, true, false, true,
+        false, true, false, true, false, true, false, true, false, true, false, true, false, true,
+        false, true, false,
     ];
 
     // Generated test data from running "bitwise" implementation. Ensures answers don't change, but
@@ -190,7 +241,7 @@ mod tests {
         39, 1, 2, 1, 6, 1, 2, 1, 3, 1,
     ];
 
-    fn test_is_correct(f: fn(usize) -> usize, answers: &'static [usize]) {
+    fn test_is_correct<T: PartialEq + std::fmt::Debug>(f: fn(usize) -> T, answers: &'static [T]) {
         for i in 0..answers.len() {
             let res = f(i + 1);
             assert_eq!(res, answers[i])
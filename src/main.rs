@@ -1,24 +1,28 @@
-#![feature(test)]
-
 use clap::error::ErrorKind;
-use clap::{CommandFactory, Parser};
-use std::cmp::max;
+use clap::{CommandFactory, Parser, Subcommand};
+use std::cell::Cell;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
 use std::thread;
 use std::time::Duration;
 use thousands::Separable;
 
-pub mod collatz;
+use collatz::stats;
+
+/// How many batches between periodic solves/s stats summaries.
+const SUMMARY_INTERVAL: usize = 20;
 
 /// collatz -- run the 3x+1 problem on some numbers or something
 #[derive(Parser)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Start running at N
-    #[clap(short, long, default_value_t = 1)]
+    #[clap(short, long, default_value_t = 1, global = true)]
     start: usize,
     /// Where to end (0 runs forever)
-    #[clap(short, long, default_value_t = 0)]
+    #[clap(short, long, default_value_t = 0, global = true)]
     end: usize,
     /// Override default batch size of `num_to_solve / (threads*2) `
     #[clap(short, long)]
@@ -28,6 +32,12 @@ struct Args {
     threads: usize,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Time each implementation over --start/--end and rank them
+    Bench,
+}
+
 impl Args {
     /// Batch size is computed after args are validated because it may be overridden.
     // It's a bit ugly here, but expedient because Clap derive uses everything in the struct
@@ -45,12 +55,37 @@ impl Args {
     }
 }
 
+/// Print a `stats::Summary` over `rates` (solves/s per batch), plus Tukey
+/// outlier counts so thread contention or thermal throttling stands out.
+fn print_rate_summary(rates: &[f32]) {
+    if rates.is_empty() {
+        return;
+    }
+    let summary = stats::Summary::new(rates);
+    let outliers = stats::tukey_outliers(rates);
+    println!(
+        "  batches: {}\tmedian: {:.3e}/s\tmean: {:.3e}/s\tmin: {:.3e}/s\tmax: {:.3e}/s\t\
+        std dev: {:.3e}\tMAD: {:.3e}\toutliers: {} mild, {} severe",
+        rates.len(),
+        summary.median,
+        summary.mean,
+        summary.min,
+        summary.max,
+        summary.std_dev,
+        summary.median_abs_dev,
+        outliers.mild,
+        outliers.severe,
+    );
+}
+
 /// Print progressive solve times and status
 fn receiver(rx: Receiver<collatz::BatchSummary>) {
     let mut solves = 0;
     let mut dur = Duration::new(0, 0);
     let mut max_steps = 0;
-    println!("Total Solves\tOverall solves/s\tBatch Duration\tBatch solves/s\tMax steps to solve");
+    let mut max_steps_num = 0;
+    let mut rates: Vec<f32> = Vec::new();
+    println!("Total Solves\tBatch Duration\tBatch solves/s\tMax steps to solve\tat N");
     loop {
         if let Result::Ok(summary) = rx.recv() {
             // Print out some stats about the batch
@@ -63,17 +98,24 @@ fn receiver(rx: Receiver<collatz::BatchSummary>) {
 
             dur += batch_dur;
             solves += batch_solves;
-            max_steps = max(summary.max_steps, max_steps);
-            let rate = solves as f32 / dur.as_secs_f32();
+            if summary.max_steps > max_steps {
+                max_steps = summary.max_steps;
+                max_steps_num = summary.max_steps_num;
+            }
+            rates.push(batch_rate);
 
             println!(
-                "{:.2e}\t\t{:.3e}\t\t\t{:.3}ms\t\t{:.2e}\t\t{}",
+                "{:.2e}\t\t{:.3}ms\t\t{:.2e}\t\t{}\t\t\t{}",
                 solves,
-                rate,
                 batch_dur.as_secs_f32() * 1000.0,
                 batch_rate,
                 max_steps,
+                max_steps_num,
             );
+
+            if rates.len() % SUMMARY_INTERVAL == 0 {
+                print_rate_summary(&rates);
+            }
         } else {
             // Done processing. Print a final summary and exit.
             let rate = solves as f32 / dur.as_secs_f32();
@@ -83,6 +125,7 @@ fn receiver(rx: Receiver<collatz::BatchSummary>) {
                 dur,
                 rate.separate_with_commas(),
             );
+            print_rate_summary(&rates);
             return;
         }
     }
@@ -97,22 +140,90 @@ fn run(start: usize, end: usize, batch_size: usize, threads: usize) {
     let receiver_thread = thread::spawn(move || {
         receiver(rx);
     });
-    collatz::solve(start, end, tx, batch_size, threads);
+    let solver = collatz::Solver::new(threads, tx);
+    solver.run_range(start, end, batch_size);
+    drop(solver);
     receiver_thread.join().unwrap();
 }
 
+/// Time `f` over `start..end`, calling it once per number (cycling through
+/// the range) rather than once per full sweep, so the resulting samples are
+/// genuinely per-number. `step` skips every other number for the
+/// "solved smaller numbers already" implementations, matching `benches`.
+fn bench_algorithm<T>(
+    name: &'static str,
+    f: fn(usize) -> T,
+    start: usize,
+    end: usize,
+    step: usize,
+) -> (&'static str, collatz::bench::BenchSamples) {
+    let first = if step == 2 && start % 2 == 0 {
+        start + 1
+    } else {
+        start
+    };
+    let current = Cell::new(first);
+    let samples = collatz::bench::bench(|| {
+        let n = current.get();
+        let next = n + step;
+        current.set(if next >= end { first } else { next });
+        f(n)
+    });
+    (name, samples)
+}
+
+/// Time every implementation over `start..end` and print them ranked
+/// fastest to slowest.
+fn run_bench(start: usize, end: usize) {
+    let mut results = vec![
+        bench_algorithm("recursive", collatz::recursive, start, end, 1),
+        bench_algorithm("simple", collatz::simple, start, end, 1),
+        bench_algorithm("shortcut", collatz::shortcut, start, end, 1),
+        bench_algorithm("faster_shortcut", collatz::faster_shortcut, start, end, 2),
+        bench_algorithm("bitwise", collatz::bitwise, start, end, 2),
+    ];
+    results.sort_by(|(_, a), (_, b)| {
+        let median_a = stats::Summary::new(&a.ns_per_call).median;
+        let median_b = stats::Summary::new(&b.ns_per_call).median;
+        median_a.partial_cmp(&median_b).unwrap()
+    });
+
+    println!(
+        "Ranked fastest to slowest over [{}, {}):",
+        start.separate_with_commas(),
+        end.separate_with_commas(),
+    );
+    for (rank, (name, samples)) in results.iter().enumerate() {
+        println!(
+            "  {}. {:<16} {}",
+            rank + 1,
+            name,
+            collatz::bench::fmt_bench_samples(samples),
+        );
+    }
+}
+
 /// Parse/validate arguments and handle any that are computed at runtime
 fn get_args() -> Args {
     let args = Args::parse();
 
-    // Ensure end == 0 || end > start
-    if args.end > 0 && args.end < args.start {
-        let mut cmd = Args::command();
-        cmd.error(
-            ErrorKind::ArgumentConflict,
-            "`end` must be 0 or greater than start",
-        )
-        .exit();
+    match args.command {
+        // `bench` always runs over a bounded, explicit range.
+        Some(Command::Bench) if args.end <= args.start => {
+            let mut cmd = Args::command();
+            cmd.error(ErrorKind::ArgumentConflict, "`end` must be greater than start")
+                .exit();
+        }
+        // Otherwise end == 0 || end > start
+        None if args.end > 0 && args.end <= args.start => {
+            let mut cmd = Args::command();
+            cmd.error(
+                ErrorKind::ArgumentConflict,
+                "`end` must be 0 or greater than start",
+            )
+            .exit();
+        }
+        _ => {}
     }
     args
 }
@@ -120,6 +231,11 @@ fn get_args() -> Args {
 fn main() {
     let args = get_args();
 
+    if matches!(args.command, Some(Command::Bench)) {
+        run_bench(args.start, args.end);
+        return;
+    }
+
     // Print message about what's about to go down
     println!(
         "Running with settings:\n  \
@@ -138,16 +254,27 @@ fn main() {
 
     // Run the thing
     if args.end == 0 {
-        // Run forever by calling `run` repeatedly`
+        // Run forever, reusing one pool/channel/receiver thread for every
+        // infini-batch instead of tearing them down every 20 billion numbers.
         let step_size = 20_000_000_000;
         let mut start = args.start;
         let mut end = start + step_size;
+
+        let (tx, rx): (
+            Sender<collatz::BatchSummary>,
+            Receiver<collatz::BatchSummary>,
+        ) = mpsc::channel();
+        thread::spawn(move || {
+            receiver(rx);
+        });
+        let solver = collatz::Solver::new(args.threads, tx);
+
         loop {
             print!(
                 "Starting infini-batch [{:.2e}, {:.2e}]\n-----\n\n",
                 start, end
             );
-            run(start, end, args.batch_size(), args.threads);
+            solver.run_range(start, end, args.batch_size());
             start = end;
             end = start + step_size;
         }
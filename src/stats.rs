@@ -0,0 +1,116 @@
+//! Small statistical summary helpers, modeled on libtest's `stats` module.
+
+/// Summary statistics for a sample of measurements.
+pub struct Summary {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub median: f32,
+    pub std_dev: f32,
+    pub median_abs_dev: f32,
+}
+
+impl Summary {
+    pub fn new(samples: &[f32]) -> Summary {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mean = sorted.iter().sum::<f32>() / sorted.len() as f32;
+        let median = percentile_of_sorted(&sorted, 50.0);
+        let variance =
+            sorted.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / sorted.len() as f32;
+
+        let mut abs_devs: Vec<f32> = sorted.iter().map(|s| (s - median).abs()).collect();
+        abs_devs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        Summary {
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            median_abs_dev: percentile_of_sorted(&abs_devs, 50.0),
+        }
+    }
+}
+
+/// Count of outliers found by `tukey_outliers`, split by severity.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct OutlierCount {
+    pub mild: usize,
+    pub severe: usize,
+}
+
+/// Classify samples using Tukey's fences: let IQR = Q3 - Q1 (quartiles taken
+/// by linear interpolation); anything beyond 1.5*IQR past a quartile is a
+/// mild outlier, and beyond 3*IQR is a severe one.
+pub fn tukey_outliers(samples: &[f32]) -> OutlierCount {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let q1 = percentile_of_sorted(&sorted, 25.0);
+    let q3 = percentile_of_sorted(&sorted, 75.0);
+    let iqr = q3 - q1;
+
+    let (mild_lo, mild_hi) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+    let (severe_lo, severe_hi) = (q1 - 3.0 * iqr, q3 + 3.0 * iqr);
+
+    let mut counts = OutlierCount::default();
+    for &s in samples {
+        if s < severe_lo || s > severe_hi {
+            counts.severe += 1;
+        } else if s < mild_lo || s > mild_hi {
+            counts.mild += 1;
+        }
+    }
+    counts
+}
+
+/// Linear-interpolation percentile of an already-sorted sample.
+fn percentile_of_sorted(sorted_samples: &[f32], pct: f32) -> f32 {
+    assert!(!sorted_samples.is_empty());
+    if sorted_samples.len() == 1 {
+        return sorted_samples[0];
+    }
+    assert!((0.0..=100.0).contains(&pct));
+    if pct == 100.0 {
+        return sorted_samples[sorted_samples.len() - 1];
+    }
+    let rank = (pct / 100.0) * (sorted_samples.len() - 1) as f32;
+    let lrank = rank.floor();
+    let d = rank - lrank;
+    let n = lrank as usize;
+    let lo = sorted_samples[n];
+    let hi = sorted_samples[n + 1];
+    lo + (hi - lo) * d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let samples = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let summary = Summary::new(&samples);
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.max, 5.0);
+        assert_eq!(summary.mean, 3.0);
+        assert_eq!(summary.median, 3.0);
+        assert_eq!(summary.median_abs_dev, 1.0);
+    }
+
+    #[test]
+    fn test_tukey_outliers() {
+        let mut samples: Vec<f32> = (1..=20).map(|n| n as f32).collect();
+        samples.push(1000.0);
+        let counts = tukey_outliers(&samples);
+        assert_eq!(counts.severe, 1);
+        assert_eq!(counts.mild, 0);
+    }
+
+    #[test]
+    fn test_percentile_of_sorted_single() {
+        assert_eq!(percentile_of_sorted(&[42.0], 50.0), 42.0);
+    }
+}